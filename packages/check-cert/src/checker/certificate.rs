@@ -0,0 +1,633 @@
+// Copyright (C) 2023 Checkmk GmbH - License: GNU General Public License v2
+// This file is part of Checkmk (https://checkmk.com). It is subject to the terms and
+// conditions defined in the file COPYING, which is part of this source code package.
+
+use std::fmt;
+
+use x509_parser::der_parser::oid;
+use x509_parser::extensions::{GeneralName, ParsedExtension};
+use x509_parser::oid_registry::{
+    OID_EC_P256, OID_HASH_SHA1, OID_NIST_EC_P384, OID_NIST_EC_P521, OID_NIST_HASH_SHA256,
+    OID_NIST_HASH_SHA384, OID_NIST_HASH_SHA512, OID_PKCS1_RSASSAPSS, OID_PKCS1_SHA1WITHRSA,
+    OID_PKCS1_SHA256WITHRSA, OID_PKCS1_SHA384WITHRSA, OID_PKCS1_SHA512WITHRSA,
+    OID_SIG_ECDSA_WITH_SHA256, OID_SIG_ECDSA_WITH_SHA384, OID_SIG_ECDSA_WITH_SHA512,
+    OID_SIG_ED25519, OID_SIG_ED448,
+};
+use x509_parser::error::X509Error;
+use x509_parser::pem::parse_x509_pem;
+use x509_parser::prelude::{FromDer, X509Certificate};
+use x509_parser::public_key::PublicKey;
+use x509_parser::signature_algorithm::SignatureAlgorithm as ParsedSignatureAlgorithm;
+
+/// Marker of the start of a PEM block, used to distinguish PEM input from raw DER.
+const PEM_START: &[u8] = b"-----BEGIN";
+
+use super::revocation::RevocationStatus;
+use super::{CheckResult, Collection};
+
+/// Named elliptic curve backing an ECDSA signature or public key.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EllipticCurve {
+    P256,
+    P384,
+    P521,
+}
+
+impl fmt::Display for EllipticCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            EllipticCurve::P256 => "P256",
+            EllipticCurve::P384 => "P384",
+            EllipticCurve::P521 => "P521",
+        };
+        write!(f, "{text}")
+    }
+}
+
+/// Named Edwards curve backing an EdDSA signature or public key.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdwardsCurve {
+    ED25519,
+    ED448,
+}
+
+impl fmt::Display for EdwardsCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            EdwardsCurve::ED25519 => "ED25519",
+            EdwardsCurve::ED448 => "ED448",
+        };
+        write!(f, "{text}")
+    }
+}
+
+/// Signature algorithm family of a certificate, as named by its `signatureAlgorithm` OID.
+///
+/// `ECDSA` and `EdDSA` carry the curve they were matched against, if any. `None` means "any
+/// curve", which is what [`SignatureAlgorithm::satisfies`] uses to allow a config to ask for the
+/// family without pinning an exact curve.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignatureAlgorithm {
+    RSA,
+    RSASSA_PSS,
+    ECDSA(Option<EllipticCurve>),
+    EdDSA(Option<EdwardsCurve>),
+}
+
+impl SignatureAlgorithm {
+    /// Whether `self` (what the certificate actually uses) satisfies `expected` (what the config
+    /// asked for). Hierarchical on the curve: an `expected` of `ECDSA(None)`/`EdDSA(None)` matches
+    /// any curve of that family, while `Some(curve)` requires an exact match.
+    fn satisfies(&self, expected: &SignatureAlgorithm) -> bool {
+        match (self, expected) {
+            (SignatureAlgorithm::ECDSA(_), SignatureAlgorithm::ECDSA(None)) => true,
+            (SignatureAlgorithm::EdDSA(_), SignatureAlgorithm::EdDSA(None)) => true,
+            _ => self == expected,
+        }
+    }
+}
+
+impl fmt::Display for SignatureAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureAlgorithm::RSA => write!(f, "RSA"),
+            SignatureAlgorithm::RSASSA_PSS => write!(f, "RSASSA_PSS"),
+            SignatureAlgorithm::ECDSA(None) => write!(f, "ECDSA"),
+            SignatureAlgorithm::ECDSA(Some(curve)) => write!(f, "ECDSA({curve})"),
+            SignatureAlgorithm::EdDSA(None) => write!(f, "EdDSA"),
+            SignatureAlgorithm::EdDSA(Some(curve)) => write!(f, "EdDSA({curve})"),
+        }
+    }
+}
+
+/// Public-key family of a certificate, as named by its `SubjectPublicKeyInfo` algorithm OID.
+///
+/// Mirrors [`SignatureAlgorithm`]'s curve handling: `EC(None)`/`EdDSA(None)` match any curve of
+/// that family, while `Some(curve)` pins the exact one.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublicKeyAlgorithm {
+    RSA,
+    EC(Option<EllipticCurve>),
+    EdDSA(Option<EdwardsCurve>),
+}
+
+impl PublicKeyAlgorithm {
+    fn satisfies(&self, expected: &PublicKeyAlgorithm) -> bool {
+        match (self, expected) {
+            (PublicKeyAlgorithm::EC(_), PublicKeyAlgorithm::EC(None)) => true,
+            (PublicKeyAlgorithm::EdDSA(_), PublicKeyAlgorithm::EdDSA(None)) => true,
+            _ => self == expected,
+        }
+    }
+}
+
+impl fmt::Display for PublicKeyAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PublicKeyAlgorithm::RSA => write!(f, "RSA"),
+            PublicKeyAlgorithm::EC(None) => write!(f, "EC"),
+            PublicKeyAlgorithm::EC(Some(curve)) => write!(f, "EC({curve})"),
+            PublicKeyAlgorithm::EdDSA(None) => write!(f, "EdDSA"),
+            PublicKeyAlgorithm::EdDSA(Some(curve)) => write!(f, "EdDSA({curve})"),
+        }
+    }
+}
+
+/// TLS protocol version whose permitted signature algorithms a certificate is checked against,
+/// in place of a single expected algorithm.
+#[allow(non_camel_case_types)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlsProfile {
+    TLSv12,
+    TLSv13,
+}
+
+impl fmt::Display for TlsProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            TlsProfile::TLSv12 => "TLSv12",
+            TlsProfile::TLSv13 => "TLSv13",
+        };
+        write!(f, "{text}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HashAlgorithm {
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Config {
+    signature_algorithm: Option<SignatureAlgorithm>,
+    pubkey_algorithm: Option<PublicKeyAlgorithm>,
+    min_rsa_bits: Option<usize>,
+    allowed_curves: Option<Vec<EllipticCurve>>,
+    tls_profile: Option<TlsProfile>,
+    issuer: Option<Vec<u8>>,
+    verify_signature: bool,
+    check_revocation: bool,
+}
+
+impl Config {
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ConfigBuilder {
+    signature_algorithm: Option<SignatureAlgorithm>,
+    pubkey_algorithm: Option<PublicKeyAlgorithm>,
+    min_rsa_bits: Option<usize>,
+    allowed_curves: Option<Vec<EllipticCurve>>,
+    tls_profile: Option<TlsProfile>,
+    issuer: Option<Vec<u8>>,
+    verify_signature: bool,
+    check_revocation: bool,
+}
+
+impl ConfigBuilder {
+    pub fn signature_algorithm(mut self, signature_algorithm: Option<SignatureAlgorithm>) -> Self {
+        self.signature_algorithm = signature_algorithm;
+        self
+    }
+
+    pub fn pubkey_algorithm(mut self, pubkey_algorithm: Option<PublicKeyAlgorithm>) -> Self {
+        self.pubkey_algorithm = pubkey_algorithm;
+        self
+    }
+
+    pub fn min_rsa_bits(mut self, min_rsa_bits: Option<usize>) -> Self {
+        self.min_rsa_bits = min_rsa_bits;
+        self
+    }
+
+    pub fn allowed_curves(mut self, allowed_curves: Option<Vec<EllipticCurve>>) -> Self {
+        self.allowed_curves = allowed_curves;
+        self
+    }
+
+    pub fn tls_profile(mut self, tls_profile: Option<TlsProfile>) -> Self {
+        self.tls_profile = tls_profile;
+        self
+    }
+
+    /// The issuer certificate (raw DER or PEM) whose public key signed the leaf. When unset,
+    /// `verify_signature` checks the leaf against its own public key, i.e. treats it as
+    /// self-signed.
+    pub fn issuer(mut self, issuer: Option<Vec<u8>>) -> Self {
+        self.issuer = issuer;
+        self
+    }
+
+    pub fn verify_signature(mut self, verify_signature: bool) -> Self {
+        self.verify_signature = verify_signature;
+        self
+    }
+
+    /// Whether to check the leaf against the OCSP responder named in its Authority Information
+    /// Access extension, asking whether `issuer` has revoked it. Requires `issuer`.
+    pub fn check_revocation(mut self, check_revocation: bool) -> Self {
+        self.check_revocation = check_revocation;
+        self
+    }
+
+    pub fn build(self) -> Config {
+        Config {
+            signature_algorithm: self.signature_algorithm,
+            pubkey_algorithm: self.pubkey_algorithm,
+            min_rsa_bits: self.min_rsa_bits,
+            allowed_curves: self.allowed_curves,
+            tls_profile: self.tls_profile,
+            issuer: self.issuer,
+            verify_signature: self.verify_signature,
+            check_revocation: self.check_revocation,
+        }
+    }
+}
+
+/// Checks a certificate, given either as raw DER or as a PEM-encoded (`-----BEGIN CERTIFICATE-----`)
+/// body, against `config`.
+pub fn check(input: &[u8], config: Config) -> Collection {
+    let mut out = Collection::default();
+
+    let der = match der_from_input(input) {
+        Ok(der) => der,
+        Err(reason) => {
+            out.add(CheckResult::crit(reason));
+            return out;
+        }
+    };
+
+    let cert = match X509Certificate::from_der(&der) {
+        Ok((_, cert)) => cert,
+        Err(_) => {
+            out.add(CheckResult::crit("Failed to parse certificate"));
+            return out;
+        }
+    };
+
+    // Parsed once up front so `signature_algorithm` can read the curve an ECDSA signature was
+    // actually made with off the issuer's key, not the leaf's. Unparsable/absent issuer input
+    // isn't reported as an error here; `verify_signature`/`check_revocation` below do that, and
+    // those are the checks for which a bad issuer is actually fatal.
+    let issuer_der;
+    let issuer_cert = match config.issuer.as_deref() {
+        Some(input) => {
+            issuer_der = der_from_input(input).unwrap_or_default();
+            X509Certificate::from_der(&issuer_der)
+                .ok()
+                .map(|(_, cert)| cert)
+        }
+        None => None,
+    };
+
+    if let Some(expected) = config.signature_algorithm {
+        match signature_algorithm(&cert, issuer_cert.as_ref()) {
+            Some(actual) if actual.satisfies(&expected) => {
+                out.add(CheckResult::ok(format!("Signature algorithm: {actual}")));
+            }
+            Some(actual) => {
+                out.add(CheckResult::warn(format!(
+                    "Signature algorithm is {actual} but expected {expected}"
+                )));
+            }
+            None => {
+                out.add(CheckResult::unknown("Signature algorithm is unknown"));
+            }
+        }
+    }
+
+    // `min_rsa_bits`/`allowed_curves` violations are reported as WARNING, same as the other
+    // algorithm-suitability checks in this function (signature algorithm, TLS profile). Unlike
+    // `verify_signature`/`check_revocation`, there's no CRITICAL tier here yet; a "critically
+    // weak" threshold (e.g. sub-1024-bit RSA) would need its own config knob.
+    if config.pubkey_algorithm.is_some()
+        || config.min_rsa_bits.is_some()
+        || config.allowed_curves.is_some()
+    {
+        match public_key_info(&cert) {
+            Some((algo, bits)) => {
+                let mut problems = Vec::new();
+
+                if let Some(expected) = config.pubkey_algorithm {
+                    if !algo.satisfies(&expected) {
+                        problems.push(format!("Public key is {algo} but expected {expected}"));
+                    }
+                }
+                if let (PublicKeyAlgorithm::RSA, Some(min_bits)) = (algo, config.min_rsa_bits) {
+                    if bits < min_bits {
+                        problems.push(format!(
+                            "Public key is RSA {bits} bits but expected at least {min_bits} bits"
+                        ));
+                    }
+                }
+                if let (PublicKeyAlgorithm::EC(curve), Some(allowed)) =
+                    (algo, &config.allowed_curves)
+                {
+                    match curve {
+                        Some(curve) if !allowed.contains(&curve) => {
+                            problems.push(format!("Public key curve {curve} is not allowed"));
+                        }
+                        Some(_) => {}
+                        None => problems.push(
+                            "Public key curve is not recognized, so it cannot be allowed"
+                                .to_string(),
+                        ),
+                    }
+                }
+
+                if problems.is_empty() {
+                    out.add(CheckResult::ok(format!("Public key: {algo} {bits} bits")));
+                } else {
+                    for problem in problems {
+                        out.add(CheckResult::warn(problem));
+                    }
+                }
+            }
+            None => {
+                out.add(CheckResult::unknown("Public key algorithm is unknown"));
+            }
+        }
+    }
+
+    if let Some(profile) = config.tls_profile {
+        match signature_algorithm(&cert, issuer_cert.as_ref()) {
+            Some(algo) => match tls_profile_violation(&cert, algo, profile) {
+                Some(reason) => out.add(CheckResult::warn(reason)),
+                None => out.add(CheckResult::ok(format!(
+                    "Signature algorithm: {algo} (allowed by {profile} profile)"
+                ))),
+            },
+            None => out.add(CheckResult::unknown("Signature algorithm is unknown")),
+        };
+    }
+
+    if config.verify_signature {
+        match verify_signature(&cert, config.issuer.as_deref()) {
+            Ok(()) => {
+                out.add(CheckResult::ok("Signature is valid"));
+            }
+            Err(reason) => {
+                out.add(CheckResult::crit(format!("Signature is invalid: {reason}")));
+            }
+        }
+    }
+
+    if config.check_revocation {
+        match check_revocation(&cert, &der, config.issuer.as_deref()) {
+            Ok(RevocationStatus::Good) => {
+                out.add(CheckResult::ok("Certificate is not revoked"));
+            }
+            Ok(RevocationStatus::Revoked(reason)) => {
+                out.add(CheckResult::crit(format!("Certificate is revoked: {reason}")));
+            }
+            Ok(RevocationStatus::Unknown(reason)) | Err(reason) => {
+                out.add(CheckResult::unknown(format!(
+                    "Could not determine revocation status: {reason}"
+                )));
+            }
+        }
+    }
+
+    out
+}
+
+/// Cryptographically verifies the leaf's signature (the `tbsCertificate || signatureAlgorithm ||
+/// signature` SignedData triple) against `issuer`'s public key, or against the leaf's own public
+/// key when `issuer` is `None` (self-signed).
+fn verify_signature(cert: &X509Certificate, issuer: Option<&[u8]>) -> Result<(), String> {
+    let issuer_der;
+    let issuer_cert = match issuer {
+        Some(input) => {
+            issuer_der = der_from_input(input)?;
+            Some(
+                X509Certificate::from_der(&issuer_der)
+                    .map_err(|_| "Failed to parse issuer certificate".to_string())?
+                    .1,
+            )
+        }
+        None => None,
+    };
+    let issuer_spki = issuer_cert.as_ref().map(|c| c.public_key());
+
+    cert.verify_signature(issuer_spki).map_err(|err| match err {
+        X509Error::SignatureVerificationError => "signature does not match".to_string(),
+        X509Error::SignatureUnsupportedAlgorithm => "unsupported signature algorithm".to_string(),
+        other => other.to_string(),
+    })
+}
+
+/// Asks the OCSP responder named in the leaf's Authority Information Access extension whether
+/// `issuer` has revoked it.
+fn check_revocation(
+    cert: &X509Certificate,
+    der: &[u8],
+    issuer: Option<&[u8]>,
+) -> Result<RevocationStatus, String> {
+    let responder_url = ocsp_responder_url(cert).ok_or_else(|| {
+        "certificate has no OCSP responder in its Authority Information Access extension"
+            .to_string()
+    })?;
+    let issuer = issuer.ok_or_else(|| "an issuer certificate is required".to_string())?;
+    let issuer_der = der_from_input(issuer)?;
+
+    let subject = openssl::x509::X509::from_der(der)
+        .map_err(|err| format!("failed to parse certificate: {err}"))?;
+    let issuer = openssl::x509::X509::from_der(&issuer_der)
+        .map_err(|err| format!("failed to parse issuer certificate: {err}"))?;
+
+    super::revocation::check(&subject, &issuer, &responder_url)
+}
+
+/// Reads the OCSP responder URL out of the leaf's Authority Information Access extension
+/// (`id-ad-ocsp` access method), if present.
+fn ocsp_responder_url(cert: &X509Certificate) -> Option<String> {
+    let id_ad_ocsp = oid!(1.3.6 .1 .5 .5 .7 .48 .1);
+
+    let aia = cert.tbs_certificate.extensions().iter().find_map(|ext| {
+        match ext.parsed_extension() {
+            ParsedExtension::AuthorityInfoAccess(aia) => Some(aia),
+            _ => None,
+        }
+    })?;
+
+    aia.accessdescs.iter().find_map(|desc| {
+        if desc.access_method != id_ad_ocsp {
+            return None;
+        }
+        match &desc.access_location {
+            GeneralName::URI(uri) => Some(uri.to_string()),
+            _ => None,
+        }
+    })
+}
+
+/// Checks `algo` (and the hash it was signed with) against the signature algorithms a given TLS
+/// version permits for certificates, returning the reason it's disallowed, if any.
+///
+/// TLS 1.2 and 1.3 both forbid SHA-1; TLS 1.3 additionally drops plain PKCS#1 v1.5 RSA in favor
+/// of RSASSA-PSS (RFC 8446 section 4.2.3).
+fn tls_profile_violation(
+    cert: &X509Certificate,
+    algo: SignatureAlgorithm,
+    profile: TlsProfile,
+) -> Option<String> {
+    if signature_hash(cert) == Some(HashAlgorithm::Sha1) {
+        return Some(format!(
+            "Signature algorithm {algo} uses SHA-1, which is not allowed in the {profile} profile"
+        ));
+    }
+
+    if profile == TlsProfile::TLSv13 && algo == SignatureAlgorithm::RSA {
+        return Some(format!(
+            "Signature algorithm {algo} (PKCS#1 v1.5) is not allowed in the {profile} profile"
+        ));
+    }
+
+    None
+}
+
+/// Determines the hash algorithm backing a signature. For RSA/ECDSA this is named directly by
+/// the `signatureAlgorithm` OID; for RSASSA-PSS it's buried in the PSS parameters instead.
+fn signature_hash(cert: &X509Certificate) -> Option<HashAlgorithm> {
+    let oid = &cert.signature_algorithm.algorithm;
+
+    if oid == &OID_PKCS1_SHA1WITHRSA {
+        return Some(HashAlgorithm::Sha1);
+    }
+    if oid == &OID_PKCS1_SHA256WITHRSA || oid == &OID_SIG_ECDSA_WITH_SHA256 {
+        return Some(HashAlgorithm::Sha256);
+    }
+    if oid == &OID_PKCS1_SHA384WITHRSA || oid == &OID_SIG_ECDSA_WITH_SHA384 {
+        return Some(HashAlgorithm::Sha384);
+    }
+    if oid == &OID_PKCS1_SHA512WITHRSA || oid == &OID_SIG_ECDSA_WITH_SHA512 {
+        return Some(HashAlgorithm::Sha512);
+    }
+    if oid == &OID_PKCS1_RSASSAPSS {
+        let ParsedSignatureAlgorithm::RSASSA_PSS(params) =
+            ParsedSignatureAlgorithm::try_from(&cert.signature_algorithm).ok()?
+        else {
+            return None;
+        };
+        let hash_oid = params.hash_algorithm_oid();
+        return if hash_oid == &OID_HASH_SHA1 {
+            Some(HashAlgorithm::Sha1)
+        } else if hash_oid == &OID_NIST_HASH_SHA256 {
+            Some(HashAlgorithm::Sha256)
+        } else if hash_oid == &OID_NIST_HASH_SHA384 {
+            Some(HashAlgorithm::Sha384)
+        } else if hash_oid == &OID_NIST_HASH_SHA512 {
+            Some(HashAlgorithm::Sha512)
+        } else {
+            None
+        };
+    }
+
+    None
+}
+
+/// Sniffs `input` for a PEM header and, if found, decodes it to DER; otherwise returns `input`
+/// unchanged, on the assumption that it's already DER.
+fn der_from_input(input: &[u8]) -> Result<Vec<u8>, String> {
+    if input.starts_with(PEM_START) {
+        let (_, pem) =
+            parse_x509_pem(input).map_err(|_| "Failed to decode PEM certificate".to_string())?;
+        Ok(pem.contents)
+    } else {
+        Ok(input.to_vec())
+    }
+}
+
+/// Determines the signature algorithm family from the certificate's `signatureAlgorithm` OID,
+/// resolving the curve for ECDSA from `issuer`'s public key parameters when `issuer` is given
+/// (falling back to `cert`'s own, i.e. treating it as self-signed, when it isn't) — the OID only
+/// ever names the hash, e.g. `ecdsa-with-SHA256`, never the curve, and it's `issuer`'s key, not
+/// `cert`'s, that actually produced the signature.
+fn signature_algorithm(
+    cert: &X509Certificate,
+    issuer: Option<&X509Certificate>,
+) -> Option<SignatureAlgorithm> {
+    let oid = &cert.signature_algorithm.algorithm;
+
+    if [
+        &OID_PKCS1_SHA1WITHRSA,
+        &OID_PKCS1_SHA256WITHRSA,
+        &OID_PKCS1_SHA384WITHRSA,
+        &OID_PKCS1_SHA512WITHRSA,
+    ]
+    .contains(&oid)
+    {
+        Some(SignatureAlgorithm::RSA)
+    } else if oid == &OID_PKCS1_RSASSAPSS {
+        Some(SignatureAlgorithm::RSASSA_PSS)
+    } else if [
+        &OID_SIG_ECDSA_WITH_SHA256,
+        &OID_SIG_ECDSA_WITH_SHA384,
+        &OID_SIG_ECDSA_WITH_SHA512,
+    ]
+    .contains(&oid)
+    {
+        Some(SignatureAlgorithm::ECDSA(ecdsa_curve(issuer.unwrap_or(cert))))
+    } else if oid == &OID_SIG_ED25519 {
+        Some(SignatureAlgorithm::EdDSA(Some(EdwardsCurve::ED25519)))
+    } else if oid == &OID_SIG_ED448 {
+        Some(SignatureAlgorithm::EdDSA(Some(EdwardsCurve::ED448)))
+    } else {
+        None
+    }
+}
+
+/// Determines the public-key family and size (in bits) from the leaf's `SubjectPublicKeyInfo`.
+/// EdDSA keys are special-cased because the x509-parser SPKI parser doesn't expose a dedicated
+/// variant for them and would otherwise surface as `PublicKey::Unknown`.
+fn public_key_info(cert: &X509Certificate) -> Option<(PublicKeyAlgorithm, usize)> {
+    let spki = &cert.tbs_certificate.subject_pki;
+
+    if spki.algorithm.algorithm == OID_SIG_ED25519 {
+        return Some((PublicKeyAlgorithm::EdDSA(Some(EdwardsCurve::ED25519)), 256));
+    }
+    if spki.algorithm.algorithm == OID_SIG_ED448 {
+        return Some((PublicKeyAlgorithm::EdDSA(Some(EdwardsCurve::ED448)), 456));
+    }
+
+    let key = spki.parsed().ok()?;
+    let bits = key.key_size();
+    match key {
+        PublicKey::RSA(_) => Some((PublicKeyAlgorithm::RSA, bits)),
+        PublicKey::EC(_) => Some((PublicKeyAlgorithm::EC(ecdsa_curve(cert)), bits)),
+        _ => None,
+    }
+}
+
+/// Reads the named curve out of the leaf's `SubjectPublicKeyInfo` (`id-ecPublicKey` parameters),
+/// the same place the SPKI parser used for public-key inspection looks it up.
+fn ecdsa_curve(cert: &X509Certificate) -> Option<EllipticCurve> {
+    let curve_oid = cert
+        .tbs_certificate
+        .subject_pki
+        .algorithm
+        .parameters
+        .as_ref()?
+        .as_oid()
+        .ok()?;
+
+    if curve_oid == OID_EC_P256 {
+        Some(EllipticCurve::P256)
+    } else if curve_oid == OID_NIST_EC_P384 {
+        Some(EllipticCurve::P384)
+    } else if curve_oid == OID_NIST_EC_P521 {
+        Some(EllipticCurve::P521)
+    } else {
+        None
+    }
+}
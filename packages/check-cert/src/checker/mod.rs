@@ -0,0 +1,9 @@
+// Copyright (C) 2023 Checkmk GmbH - License: GNU General Public License v2
+// This file is part of Checkmk (https://checkmk.com). It is subject to the terms and
+// conditions defined in the file COPYING, which is part of this source code package.
+
+pub mod certificate;
+mod output;
+pub mod revocation;
+
+pub use output::{CheckResult, Collection, State};
@@ -0,0 +1,130 @@
+// Copyright (C) 2023 Checkmk GmbH - License: GNU General Public License v2
+// This file is part of Checkmk (https://checkmk.com). It is subject to the terms and
+// conditions defined in the file COPYING, which is part of this source code package.
+
+use std::fmt;
+
+/// Severity of a single check outcome, following Checkmk's plugin states.
+///
+/// Declaration order doubles as the `Ord` used by [`Collection::worst_state`] to pick the worst
+/// of several results, so it follows Checkmk's `State.worst` semantics rather than the numeric
+/// Nagios plugin return codes (where UNKNOWN is 3, above CRITICAL's 2): CRITICAL is the worst
+/// state, with UNKNOWN below it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum State {
+    Ok,
+    Warn,
+    Unknown,
+    Crit,
+}
+
+impl State {
+    fn marker(self) -> &'static str {
+        match self {
+            State::Ok => "",
+            State::Warn => "(!)",
+            State::Crit => "(!!)",
+            State::Unknown => "(?)",
+        }
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            State::Ok => "OK",
+            State::Warn => "WARNING",
+            State::Crit => "CRITICAL",
+            State::Unknown => "UNKNOWN",
+        };
+        write!(f, "{text}")
+    }
+}
+
+/// One line of check output together with the state it represents.
+#[derive(Debug, Clone)]
+pub struct CheckResult {
+    state: State,
+    text: String,
+}
+
+impl CheckResult {
+    pub fn ok(text: impl Into<String>) -> Self {
+        Self {
+            state: State::Ok,
+            text: text.into(),
+        }
+    }
+
+    pub fn warn(text: impl Into<String>) -> Self {
+        Self {
+            state: State::Warn,
+            text: text.into(),
+        }
+    }
+
+    pub fn crit(text: impl Into<String>) -> Self {
+        Self {
+            state: State::Crit,
+            text: text.into(),
+        }
+    }
+
+    pub fn unknown(text: impl Into<String>) -> Self {
+        Self {
+            state: State::Unknown,
+            text: text.into(),
+        }
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+}
+
+/// The accumulated output of a check: zero or more [`CheckResult`]s collapsed into the single
+/// summary line Checkmk expects from a plugin, with the worst state winning.
+#[derive(Debug, Clone, Default)]
+pub struct Collection {
+    results: Vec<CheckResult>,
+}
+
+impl Collection {
+    pub fn add(&mut self, result: CheckResult) -> &mut Self {
+        self.results.push(result);
+        self
+    }
+
+    pub fn worst_state(&self) -> State {
+        self.results
+            .iter()
+            .map(CheckResult::state)
+            .max()
+            .unwrap_or(State::Ok)
+    }
+}
+
+impl fmt::Display for Collection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.worst_state() {
+            State::Ok => {
+                let lines: Vec<&str> = self.results.iter().map(|r| r.text.as_str()).collect();
+                write!(f, "OK\n{}", lines.join("\n"))
+            }
+            _ => {
+                // OK results still contribute their text (unprefixed, same as the all-OK branch
+                // above) so a passing check isn't silently dropped just because some other check
+                // in the same run came back worse.
+                let parts: Vec<String> = self
+                    .results
+                    .iter()
+                    .map(|r| match r.state {
+                        State::Ok => r.text.clone(),
+                        state => format!("{state} - {} {}", r.text, state.marker()),
+                    })
+                    .collect();
+                write!(f, "{}", parts.join(", "))
+            }
+        }
+    }
+}
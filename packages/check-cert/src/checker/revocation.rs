@@ -0,0 +1,159 @@
+// Copyright (C) 2023 Checkmk GmbH - License: GNU General Public License v2
+// This file is part of Checkmk (https://checkmk.com). It is subject to the terms and
+// conditions defined in the file COPYING, which is part of this source code package.
+
+//! OCSP (RFC 6960) revocation checking for a single certificate.
+
+use std::io::Read;
+use std::time::Duration;
+
+use openssl::hash::MessageDigest;
+use openssl::ocsp::{
+    OcspCertId, OcspCertStatus, OcspFlag, OcspRequest, OcspResponse, OcspResponseStatus,
+};
+use openssl::stack::Stack;
+use openssl::x509::store::X509StoreBuilder;
+use openssl::x509::X509;
+
+/// How long to wait for the OCSP responder to answer before giving up.
+const RESPONDER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of checking a certificate against an OCSP responder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevocationStatus {
+    /// The responder vouches that the certificate has not been revoked.
+    Good,
+    /// The responder reports the certificate as revoked, with a human-readable reason.
+    Revoked(String),
+    /// The responder doesn't know the certificate, or answered with something we can't use.
+    Unknown(String),
+}
+
+/// Asks `responder_url` (an OCSP responder named in `cert`'s Authority Information Access
+/// extension) whether `cert`, issued by `issuer`, has been revoked.
+///
+/// Builds an `OCSPRequest` for `cert`'s serial, POSTs its DER encoding to `responder_url`, then
+/// verifies the reply against `issuer` and reads the signed `tbsResponseData`, mapping its
+/// certificate status to [`RevocationStatus`]. A forged or substituted response is reported as an
+/// error rather than trusted.
+///
+/// The request carries no nonce (RFC 8954): the `openssl` crate's OCSP bindings don't expose
+/// `OCSP_basic_add1_nonce`/`OCSP_check_nonce`, so a malicious responder could replay a cached
+/// `GOOD` response from before a revocation. `next_update`-based freshness (not yet checked here
+/// either) would bound the same exposure.
+pub fn check(cert: &X509, issuer: &X509, responder_url: &str) -> Result<RevocationStatus, String> {
+    let cert_id = OcspCertId::from_cert(MessageDigest::sha1(), cert, issuer)
+        .map_err(|err| format!("failed to build OCSP request: {err}"))?;
+
+    let mut request =
+        OcspRequest::new().map_err(|err| format!("failed to build OCSP request: {err}"))?;
+    request
+        .add_id(cert_id)
+        .map_err(|err| format!("failed to build OCSP request: {err}"))?;
+    let request_der = request
+        .to_der()
+        .map_err(|err| format!("failed to encode OCSP request: {err}"))?;
+
+    let response_der = post(responder_url, &request_der)?;
+
+    let cert_id = OcspCertId::from_cert(MessageDigest::sha1(), cert, issuer)
+        .map_err(|err| format!("failed to build OCSP request: {err}"))?;
+    status_from_response(&response_der, &cert_id, issuer)
+}
+
+/// POSTs an OCSP request to `url` and returns the raw response body, per the HTTP transport
+/// defined in RFC 6960 appendix A (`Content-Type: application/ocsp-request`).
+fn post(url: &str, request_der: &[u8]) -> Result<Vec<u8>, String> {
+    let response = ureq::post(url)
+        .timeout(RESPONDER_TIMEOUT)
+        .set("Content-Type", "application/ocsp-request")
+        .send_bytes(request_der)
+        .map_err(|err| format!("OCSP request to {url} failed: {err}"))?;
+
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut body)
+        .map_err(|err| format!("failed to read OCSP response from {url}: {err}"))?;
+    Ok(body)
+}
+
+/// Verifies `basic`'s signature against a trust store containing only `issuer`, which covers both
+/// the common case (the CA answers OCSP requests itself) and delegated OCSP signing (the CA issued
+/// a dedicated responder certificate, which the response carries and which chains to `issuer`).
+/// Without this, a MITM'd or forged `GOOD` response would be trusted outright.
+fn verify_response(basic: &openssl::ocsp::OcspBasicResponseRef, issuer: &X509) -> Result<(), String> {
+    let mut store_builder = X509StoreBuilder::new()
+        .map_err(|err| format!("failed to build OCSP trust store: {err}"))?;
+    store_builder
+        .add_cert(issuer.to_owned())
+        .map_err(|err| format!("failed to build OCSP trust store: {err}"))?;
+    let store = store_builder.build();
+    let untrusted = Stack::new().map_err(|err| format!("failed to build OCSP trust store: {err}"))?;
+
+    basic
+        .verify(&untrusted, &store, OcspFlag::empty())
+        .map_err(|err| format!("OCSP response signature verification failed: {err}"))
+}
+
+/// Parses a DER-encoded OCSP response, verifies it was signed by `issuer`, and maps the status of
+/// `cert_id` within it.
+fn status_from_response(
+    der: &[u8],
+    cert_id: &openssl::ocsp::OcspCertIdRef,
+    issuer: &X509,
+) -> Result<RevocationStatus, String> {
+    let response =
+        OcspResponse::from_der(der).map_err(|err| format!("failed to parse OCSP response: {err}"))?;
+
+    if response.status() != OcspResponseStatus::SUCCESSFUL {
+        return Ok(RevocationStatus::Unknown(format!(
+            "OCSP responder returned status {:?}",
+            response.status()
+        )));
+    }
+
+    let basic = response
+        .basic()
+        .map_err(|err| format!("failed to parse OCSP response body: {err}"))?;
+
+    verify_response(&basic, issuer)?;
+
+    let status = basic
+        .find_status(cert_id)
+        .ok_or_else(|| "OCSP response does not cover this certificate".to_string())?;
+
+    match status.status {
+        s if s == OcspCertStatus::GOOD => Ok(RevocationStatus::Good),
+        s if s == OcspCertStatus::REVOKED => {
+            let when = status
+                .revocation_time
+                .map(|time| time.to_string())
+                .unwrap_or_else(|| "unknown time".to_string());
+            Ok(RevocationStatus::Revoked(format!(
+                "revoked at {when} ({})",
+                revocation_reason(status.reason)
+            )))
+        }
+        _ => Ok(RevocationStatus::Unknown(
+            "OCSP responder does not know this certificate".to_string(),
+        )),
+    }
+}
+
+/// Human-readable name for an OCSP revocation reason code (RFC 5280 section 5.3.1).
+fn revocation_reason(reason: openssl::ocsp::OcspRevokedStatus) -> &'static str {
+    use openssl::ocsp::OcspRevokedStatus as Reason;
+
+    match reason {
+        r if r == Reason::UNSPECIFIED => "unspecified",
+        r if r == Reason::KEY_COMPROMISE => "key compromise",
+        r if r == Reason::CA_COMPROMISE => "CA compromise",
+        r if r == Reason::AFFILIATION_CHANGED => "affiliation changed",
+        r if r == Reason::STATUS_SUPERSEDED => "superseded",
+        r if r == Reason::STATUS_CESSATION_OF_OPERATION => "cessation of operation",
+        r if r == Reason::STATUS_CERTIFICATE_HOLD => "certificate hold",
+        r if r == Reason::REMOVE_FROM_CRL => "removed from CRL",
+        _ => "no reason given",
+    }
+}
@@ -2,7 +2,13 @@
 // This file is part of Checkmk (https://checkmk.com). It is subject to the terms and
 // conditions defined in the file COPYING, which is part of this source code package.
 
-use check_cert::checker::certificate::{self, Config as CertConfig, SignatureAlgorithm};
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::sync::Mutex;
+
+use check_cert::checker::certificate::{
+    self, Config as CertConfig, EllipticCurve, PublicKeyAlgorithm, SignatureAlgorithm, TlsProfile,
+};
 
 fn as_der(crt: &[u8]) -> Vec<u8> {
     openssl::x509::X509::from_pem(crt)
@@ -11,6 +17,29 @@ fn as_der(crt: &[u8]) -> Vec<u8> {
         .unwrap()
 }
 
+/// Serializes the tests that bind `127.0.0.1:18091`, since that address is fixed (baked into
+/// `ocsp-leaf-cert.pem`'s Authority Information Access extension) and tests run concurrently.
+static OCSP_RESPONDER_PORT: Mutex<()> = Mutex::new(());
+
+/// Serves a single OCSP response over HTTP on `127.0.0.1:18091`, the address baked into
+/// `ocsp-leaf-cert.pem`'s Authority Information Access extension, then shuts down.
+fn serve_one_ocsp_response(response_der: &'static [u8]) -> std::thread::JoinHandle<()> {
+    let listener = TcpListener::bind("127.0.0.1:18091").expect("Cannot fail");
+    std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().expect("Cannot fail");
+
+        let mut buf = [0u8; 4096];
+        let _ = stream.read(&mut buf).expect("Cannot fail");
+
+        let header = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/ocsp-response\r\nContent-Length: {}\r\n\r\n",
+            response_der.len()
+        );
+        stream.write_all(header.as_bytes()).expect("Cannot fail");
+        stream.write_all(response_der).expect("Cannot fail");
+    })
+}
+
 #[test]
 fn test_signature_algorithm_rsa() {
     static DER: &[u8] = include_bytes!("../assets/cert.der");
@@ -24,6 +53,19 @@ fn test_signature_algorithm_rsa() {
     assert_eq!(out.to_string(), format!("OK\nSignature algorithm: RSA"));
 }
 
+#[test]
+fn test_signature_algorithm_rsa_sha384() {
+    static PEM: &[u8] = include_bytes!("../assets/ee-rsa-sha384-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .signature_algorithm(Some(SignatureAlgorithm::RSA))
+            .build(),
+    );
+    assert_eq!(out.to_string(), format!("OK\nSignature algorithm: RSA"));
+}
+
 #[test]
 fn test_signature_algorithm_rsassa_pss_sha256() {
     // from openssl repo
@@ -93,3 +135,386 @@ fn test_signature_algorithm_rsassa_pss_sha1_neg() {
         format!("WARNING - Signature algorithm is RSASSA_PSS but expected RSA (!)")
     );
 }
+
+#[test]
+fn test_signature_algorithm_ecdsa_p256_any_curve() {
+    static PEM: &[u8] = include_bytes!("../assets/ee-ecdsa-p256-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .signature_algorithm(Some(SignatureAlgorithm::ECDSA(None)))
+            .build(),
+    );
+    assert_eq!(out.to_string(), format!("OK\nSignature algorithm: ECDSA(P256)"));
+}
+
+#[test]
+fn test_signature_algorithm_ecdsa_p256_exact_curve() {
+    static PEM: &[u8] = include_bytes!("../assets/ee-ecdsa-p256-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .signature_algorithm(Some(SignatureAlgorithm::ECDSA(Some(EllipticCurve::P256))))
+            .build(),
+    );
+    assert_eq!(out.to_string(), format!("OK\nSignature algorithm: ECDSA(P256)"));
+}
+
+#[test]
+fn test_signature_algorithm_ecdsa_curve_from_issuer() {
+    // Leaf key is P256, but it was signed by a P384 CA; the reported curve should name the one
+    // that produced the signature, not the leaf's own key.
+    static LEAF: &[u8] = include_bytes!("../assets/ee-ecdsa-p256-signed-by-p384-ca-cert.pem");
+    static CA: &[u8] = include_bytes!("../assets/ca-ecdsa-p384-cert.pem");
+
+    let out = certificate::check(
+        &as_der(LEAF),
+        CertConfig::builder()
+            .issuer(Some(CA.to_vec()))
+            .signature_algorithm(Some(SignatureAlgorithm::ECDSA(None)))
+            .build(),
+    );
+    assert_eq!(out.to_string(), format!("OK\nSignature algorithm: ECDSA(P384)"));
+}
+
+#[test]
+fn test_signature_algorithm_ecdsa_wrong_curve_neg() {
+    static PEM: &[u8] = include_bytes!("../assets/ee-ecdsa-p256-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .signature_algorithm(Some(SignatureAlgorithm::ECDSA(Some(EllipticCurve::P384))))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!("WARNING - Signature algorithm is ECDSA(P256) but expected ECDSA(P384) (!)")
+    );
+}
+
+#[test]
+fn test_signature_algorithm_eddsa_ed25519() {
+    static PEM: &[u8] = include_bytes!("../assets/ee-ed25519-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .signature_algorithm(Some(SignatureAlgorithm::EdDSA(None)))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!("OK\nSignature algorithm: EdDSA(ED25519)")
+    );
+}
+
+#[test]
+fn test_pubkey_rsa_2048() {
+    static DER: &[u8] = include_bytes!("../assets/cert.der");
+
+    let out = certificate::check(
+        DER,
+        CertConfig::builder()
+            .pubkey_algorithm(Some(PublicKeyAlgorithm::RSA))
+            .min_rsa_bits(Some(2048))
+            .build(),
+    );
+    assert_eq!(out.to_string(), format!("OK\nPublic key: RSA 2048 bits"));
+}
+
+#[test]
+fn test_pubkey_rsa_weak_neg() {
+    static PEM: &[u8] = include_bytes!("../assets/weak-rsa-1024-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder().min_rsa_bits(Some(2048)).build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!("WARNING - Public key is RSA 1024 bits but expected at least 2048 bits (!)")
+    );
+}
+
+#[test]
+fn test_combined_ok_and_warning_keeps_ok_line() {
+    static PEM: &[u8] = include_bytes!("../assets/weak-rsa-1024-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .signature_algorithm(Some(SignatureAlgorithm::RSA))
+            .min_rsa_bits(Some(2048))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!(
+            "Signature algorithm: RSA, WARNING - Public key is RSA 1024 bits but expected at least 2048 bits (!)"
+        )
+    );
+}
+
+#[test]
+fn test_pubkey_ec_disallowed_curve_neg() {
+    static PEM: &[u8] = include_bytes!("../assets/ee-ecdsa-p384-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .allowed_curves(Some(vec![EllipticCurve::P256]))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!("WARNING - Public key curve P384 is not allowed (!)")
+    );
+}
+
+#[test]
+fn test_pubkey_ec_unrecognized_curve_neg() {
+    static PEM: &[u8] = include_bytes!("../assets/ee-ecdsa-secp256k1-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .allowed_curves(Some(vec![EllipticCurve::P256]))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        "WARNING - Public key curve is not recognized, so it cannot be allowed (!)"
+    );
+}
+
+#[test]
+fn test_tls_profile_v13_rejects_sha1() {
+    // from openssl repo
+    static PEM: &[u8] = include_bytes!("../assets/ee-pss-sha1-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .tls_profile(Some(TlsProfile::TLSv13))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!(
+            "WARNING - Signature algorithm RSASSA_PSS uses SHA-1, which is not allowed in the TLSv13 profile (!)"
+        )
+    );
+}
+
+#[test]
+fn test_tls_profile_v13_rejects_pkcs1() {
+    static DER: &[u8] = include_bytes!("../assets/cert.der");
+
+    let out = certificate::check(
+        DER,
+        CertConfig::builder()
+            .tls_profile(Some(TlsProfile::TLSv13))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!(
+            "WARNING - Signature algorithm RSA (PKCS#1 v1.5) is not allowed in the TLSv13 profile (!)"
+        )
+    );
+}
+
+#[test]
+fn test_tls_profile_v13_allows_rsassa_pss_sha256() {
+    // from openssl repo
+    static PEM: &[u8] = include_bytes!("../assets/ee-pss-sha256-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .tls_profile(Some(TlsProfile::TLSv13))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!("OK\nSignature algorithm: RSASSA_PSS (allowed by TLSv13 profile)")
+    );
+}
+
+#[test]
+fn test_tls_profile_v13_allows_ecdsa_p256_sha256() {
+    static PEM: &[u8] = include_bytes!("../assets/ee-ecdsa-p256-cert.pem");
+
+    let out = certificate::check(
+        &as_der(PEM),
+        CertConfig::builder()
+            .tls_profile(Some(TlsProfile::TLSv13))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!("OK\nSignature algorithm: ECDSA(P256) (allowed by TLSv13 profile)")
+    );
+}
+
+#[test]
+fn test_check_accepts_pem_directly() {
+    // from openssl repo
+    static PEM: &[u8] = include_bytes!("../assets/ee-pss-sha256-cert.pem");
+
+    let out = certificate::check(
+        PEM,
+        CertConfig::builder()
+            .signature_algorithm(Some(SignatureAlgorithm::RSASSA_PSS))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!("OK\nSignature algorithm: RSASSA_PSS")
+    );
+}
+
+#[test]
+fn test_check_malformed_input() {
+    let out = certificate::check(b"not a certificate", CertConfig::builder().build());
+    assert_eq!(out.to_string(), format!("CRITICAL - Failed to parse certificate (!!)"));
+}
+
+#[test]
+fn test_check_malformed_pem_input() {
+    let out = certificate::check(
+        b"-----BEGIN CERTIFICATE-----\nnot base64\n-----END CERTIFICATE-----\n",
+        CertConfig::builder().build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!("CRITICAL - Failed to decode PEM certificate (!!)")
+    );
+}
+
+#[test]
+fn test_verify_signature_self_signed() {
+    static DER: &[u8] = include_bytes!("../assets/cert.der");
+
+    let out = certificate::check(DER, CertConfig::builder().verify_signature(true).build());
+    assert_eq!(out.to_string(), format!("OK\nSignature is valid"));
+}
+
+#[test]
+fn test_verify_signature_with_issuer() {
+    static LEAF: &[u8] = include_bytes!("../assets/leaf-cert.pem");
+    static CA: &[u8] = include_bytes!("../assets/ca-cert.pem");
+
+    let out = certificate::check(
+        LEAF,
+        CertConfig::builder()
+            .verify_signature(true)
+            .issuer(Some(CA.to_vec()))
+            .build(),
+    );
+    assert_eq!(out.to_string(), format!("OK\nSignature is valid"));
+}
+
+#[test]
+fn test_verify_signature_wrong_issuer_neg() {
+    static LEAF: &[u8] = include_bytes!("../assets/leaf-cert.pem");
+    // cert.der is not the CA that signed leaf-cert.pem
+    static WRONG_ISSUER: &[u8] = include_bytes!("../assets/cert.der");
+
+    let out = certificate::check(
+        LEAF,
+        CertConfig::builder()
+            .verify_signature(true)
+            .issuer(Some(WRONG_ISSUER.to_vec()))
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!("CRITICAL - Signature is invalid: signature does not match (!!)")
+    );
+}
+
+#[test]
+fn test_check_revocation_good() {
+    static LEAF: &[u8] = include_bytes!("../assets/ocsp-leaf-cert.pem");
+    static CA: &[u8] = include_bytes!("../assets/ca-cert.pem");
+    static OCSP_RESPONSE: &[u8] = include_bytes!("../assets/ocsp-response-good.der");
+
+    let _guard = OCSP_RESPONDER_PORT.lock().expect("Cannot fail");
+    let server = serve_one_ocsp_response(OCSP_RESPONSE);
+
+    let out = certificate::check(
+        LEAF,
+        CertConfig::builder()
+            .issuer(Some(CA.to_vec()))
+            .check_revocation(true)
+            .build(),
+    );
+
+    server.join().expect("Cannot fail");
+    assert_eq!(out.to_string(), format!("OK\nCertificate is not revoked"));
+}
+
+#[test]
+fn test_check_revocation_revoked() {
+    static LEAF: &[u8] = include_bytes!("../assets/ocsp-leaf-cert.pem");
+    static CA: &[u8] = include_bytes!("../assets/ca-cert.pem");
+    static OCSP_RESPONSE: &[u8] = include_bytes!("../assets/ocsp-response-revoked.der");
+
+    let _guard = OCSP_RESPONDER_PORT.lock().expect("Cannot fail");
+    let server = serve_one_ocsp_response(OCSP_RESPONSE);
+
+    let out = certificate::check(
+        LEAF,
+        CertConfig::builder()
+            .issuer(Some(CA.to_vec()))
+            .check_revocation(true)
+            .build(),
+    );
+
+    server.join().expect("Cannot fail");
+    assert_eq!(
+        out.to_string(),
+        format!(
+            "CRITICAL - Certificate is revoked: revoked at Jul 27 04:04:08 2026 GMT (key compromise) (!!)"
+        )
+    );
+}
+
+#[test]
+fn test_check_revocation_no_issuer_neg() {
+    static LEAF: &[u8] = include_bytes!("../assets/ocsp-leaf-cert.pem");
+
+    let out = certificate::check(
+        LEAF,
+        CertConfig::builder().check_revocation(true).build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!("UNKNOWN - Could not determine revocation status: an issuer certificate is required (?)")
+    );
+}
+
+#[test]
+fn test_check_revocation_no_aia_neg() {
+    // cert.der has no Authority Information Access extension.
+    static DER: &[u8] = include_bytes!("../assets/cert.der");
+    static CA: &[u8] = include_bytes!("../assets/ca-cert.pem");
+
+    let out = certificate::check(
+        DER,
+        CertConfig::builder()
+            .issuer(Some(CA.to_vec()))
+            .check_revocation(true)
+            .build(),
+    );
+    assert_eq!(
+        out.to_string(),
+        format!(
+            "UNKNOWN - Could not determine revocation status: certificate has no OCSP responder in its Authority Information Access extension (?)"
+        )
+    );
+}
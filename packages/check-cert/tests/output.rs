@@ -0,0 +1,14 @@
+// Copyright (C) 2023 Checkmk GmbH - License: GNU General Public License v2
+// This file is part of Checkmk (https://checkmk.com). It is subject to the terms and
+// conditions defined in the file COPYING, which is part of this source code package.
+
+use check_cert::checker::{CheckResult, Collection, State};
+
+#[test]
+fn test_worst_state_crit_beats_unknown() {
+    let mut out = Collection::default();
+    out.add(CheckResult::crit("something is broken"));
+    out.add(CheckResult::unknown("something else couldn't be determined"));
+
+    assert_eq!(out.worst_state(), State::Crit);
+}